@@ -0,0 +1,694 @@
+use std::iter::zip;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rayon::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+/// Describes the colors that our Master mind game supports.
+/// You can use Red, Brown, Yellow, Green, Black, White, Orange, Blue, Purple,
+/// Pink, Cyan, Gray, Magenta, Lime, Teal, Maroon, Navy, Olive, Silver or None.
+pub enum Color {
+    Red,
+    Brown,
+    Yellow,
+    Green,
+    Black,
+    White,
+    Orange,
+    Blue,
+    Purple,
+    Pink,
+    Cyan,
+    Gray,
+    Magenta,
+    Lime,
+    Teal,
+    Maroon,
+    Navy,
+    Olive,
+    Silver,
+    None,
+}
+
+#[derive(Debug, PartialEq, Ord, Eq, PartialOrd, Clone, Copy)]
+/// Used when determining which part of the guess has same color and place, same color but wrong
+/// position or just plain wrong.
+pub enum Hint {
+    CorrectlyPlaced,
+    CorrectColorButWrong,
+}
+
+/// A single played guess together with the hints it scored.
+pub type Round = (Vec<Color>, Vec<Hint>);
+
+/// Every color a code may be drawn from, in a fixed order. The active palette of
+/// a [GameConfig] is always a prefix of this list. Twenty colors are named here
+/// so that `--colors`/the colors prompt can honor the full 2-20 range; see
+/// [encode_code] for how that many still fits in a packed code.
+pub const ALL_COLORS: [Color; 20] = [
+    Color::Red, Color::Brown, Color::Yellow, Color::Green, Color::Black, Color::White,
+    Color::Orange, Color::Blue, Color::Purple, Color::Pink, Color::Cyan, Color::Gray,
+    Color::Magenta, Color::Lime, Color::Teal, Color::Maroon, Color::Navy, Color::Olive,
+    Color::Silver, Color::None,
+];
+
+/// The number of colors [GameConfig::default] uses: nine, as many as the
+/// original board this game was built around had, kept as the default even
+/// though [ALL_COLORS] now has room for up to twenty. The default palette is
+/// the first nine of [ALL_COLORS] (Red through Purple), so it does not include
+/// [Color::None].
+pub const DEFAULT_COLORS: usize = 9;
+
+/// The options that describe a single game of Mastermind, following the classic
+/// Rosetta Code task: the player chooses the code length, how many colors are in
+/// play, how many guesses they get, and whether a color may repeat in the code.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// Number of pegs in the secret code (4-10).
+    pub code_length: usize,
+    /// The palette the code is drawn from, a prefix of [ALL_COLORS].
+    pub colors: Vec<Color>,
+    /// Maximum number of guesses before the game is lost (7-20).
+    pub max_guesses: usize,
+    /// Whether the same color may appear more than once in the code.
+    pub allow_repeats: bool,
+}
+
+impl Default for GameConfig {
+    /// The original game: a 5-peg code over the default nine colors, twelve
+    /// guesses and repeats allowed.
+    fn default() -> Self {
+        GameConfig {
+            code_length: 5,
+            colors: ALL_COLORS[..DEFAULT_COLORS].to_vec(),
+            max_guesses: 12,
+            allow_repeats: true,
+        }
+    }
+}
+
+impl GameConfig {
+    /// A code without repeats can be no longer than the palette it is drawn from;
+    /// cap the length and tell the player when that happens.
+    pub fn reconcile(&mut self) {
+        if !self.allow_repeats && self.code_length > self.colors.len() {
+            println!("Capping code length to {} because repeats are disabled.", self.colors.len());
+            self.code_length = self.colors.len();
+        }
+    }
+}
+
+/// Given a string, get the color it names, or `None` if it isn't one of [ALL_COLORS].
+pub fn string_to_color(input: &str) -> Option<Color> {
+    Some(match input.to_lowercase().as_str() {
+        "red" => Color::Red,
+        "brown" => Color::Brown,
+        "yellow" => Color::Yellow,
+        "green" => Color::Green,
+        "black" => Color::Black,
+        "white" => Color::White,
+        "orange" => Color::Orange,
+        "blue" => Color::Blue,
+        "purple" => Color::Purple,
+        "pink" => Color::Pink,
+        "cyan" => Color::Cyan,
+        "gray" => Color::Gray,
+        "magenta" => Color::Magenta,
+        "lime" => Color::Lime,
+        "teal" => Color::Teal,
+        "maroon" => Color::Maroon,
+        "navy" => Color::Navy,
+        "olive" => Color::Olive,
+        "silver" => Color::Silver,
+        "none" => Color::None,
+        _ => return None,
+    })
+}
+
+/// Given a [Color], get its canonical display name. Inverse of [string_to_color].
+pub fn color_to_string(color: &Color) -> &'static str {
+    match color {
+        Color::Red => "Red",
+        Color::Brown => "Brown",
+        Color::Yellow => "Yellow",
+        Color::Green => "Green",
+        Color::Black => "Black",
+        Color::White => "White",
+        Color::Orange => "Orange",
+        Color::Blue => "Blue",
+        Color::Purple => "Purple",
+        Color::Pink => "Pink",
+        Color::Cyan => "Cyan",
+        Color::Gray => "Gray",
+        Color::Magenta => "Magenta",
+        Color::Lime => "Lime",
+        Color::Teal => "Teal",
+        Color::Maroon => "Maroon",
+        Color::Navy => "Navy",
+        Color::Olive => "Olive",
+        Color::Silver => "Silver",
+        Color::None => "None",
+    }
+}
+
+/// checks if the guess and code are the same.
+fn is_correct(guess: &[Color], code: &[Color]) -> bool {
+    if guess.len() != code.len() { return false; }
+    zip(guess, code).fold(true, |acc, (guess, code)| { guess == code && acc })
+}
+
+// Get hints based on guess and the correct code.
+fn get_hints(guess: &[Color], code: &[Color]) -> Vec<Hint> {
+    let mut hints = vec!();
+    let mut used = vec![false; code.len()];
+    // CorrectColorButWrong can shadow CorrectlyPlaced, therefore
+    // we must first find all the CorrectlyPlaced before looking at
+    // CorrectColorButWrong.
+    for (i, color) in guess.iter().enumerate() {
+        if *color == code[i] {
+            hints.push(Hint::CorrectlyPlaced);
+            used[i] = true;
+        }
+    }
+    for (i, color) in guess.iter().enumerate() {
+        if *color == code[i] { continue }
+        let found = zip(code, &used)
+            .enumerate()
+            .find(|(_, (code, used))| color == *code && !**used)
+            .map(|(i, _)| i);
+        if let Some(i) = found {
+            used[i] = true;
+            hints.push(Hint::CorrectColorButWrong);
+        }
+    }
+    hints.sort();
+    hints
+}
+
+/// Draw a random secret code for `config`: with repeats allowed, each peg is drawn
+/// independently; without repeats, pegs are drawn from the palette without
+/// replacement and then shuffled into position.
+fn get_code(config: &GameConfig) -> Vec<Color> {
+    let mut rng = rand::rng();
+    if config.allow_repeats {
+        (0..config.code_length)
+            .map(|_| *config.colors.choose(&mut rng).unwrap())
+            .collect()
+    } else {
+        // `choose_multiple` keeps the palette order, so shuffle to randomize both
+        // which colors appear and where.
+        let mut code: Vec<Color> =
+            config.colors.choose_multiple(&mut rng, config.code_length).copied().collect();
+        code.shuffle(&mut rng);
+        code
+    }
+}
+
+/// Why a guess string could not be turned into a guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    /// The guess did not have exactly `expected` space-separated tokens.
+    WrongLength { expected: usize, got: usize },
+    /// `token` is not the name of a color in the active palette.
+    UnknownColor(String),
+}
+
+impl std::fmt::Display for GuessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GuessError::WrongLength { expected, got } =>
+                write!(f, "expected {expected} colors, got {got}"),
+            GuessError::UnknownColor(token) =>
+                write!(f, "'{token}' is not one of the active colors"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+/// Parse a space-separated guess such as `"red green none"` into [Color]s, checking
+/// it has [GameConfig::code_length] tokens drawn from the active palette.
+fn parse_guess(input: &str, config: &GameConfig) -> Result<Vec<Color>, GuessError> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    if tokens.len() != config.code_length {
+        return Err(GuessError::WrongLength { expected: config.code_length, got: tokens.len() });
+    }
+    tokens.into_iter()
+        .map(|token| {
+            let color = string_to_color(token)
+                .filter(|color| config.colors.contains(color))
+                .ok_or_else(|| GuessError::UnknownColor(token.to_string()))?;
+            Ok(color)
+        })
+        .collect()
+}
+
+/// A single game of Mastermind: a secret code, the config it was drawn from, and
+/// the history of guesses played against it so far. Free of I/O, so the rules can
+/// be driven from a terminal loop, tests, or any other frontend.
+pub struct Game {
+    pub config: GameConfig,
+    secret: Vec<Color>,
+    pub history: Vec<Round>,
+}
+
+impl Game {
+    /// Start a new game: draw a secret code from `config`.
+    pub fn new(config: GameConfig) -> Game {
+        let secret = get_code(&config);
+        Game { config, secret, history: vec![] }
+    }
+
+    /// Start a new game with a chosen secret instead of a random one, so tests
+    /// can exercise scoring against exact codes (duplicate colors, `None` pegs,
+    /// and the like) without depending on [get_code]'s randomness.
+    pub fn with_secret(config: GameConfig, secret: Vec<Color>) -> Game {
+        Game { config, secret, history: vec![] }
+    }
+
+    /// Parse and score `input` against the secret code, recording it in
+    /// [Game::history]. Rejects guesses with the wrong number of pegs or a color
+    /// outside the active palette without consuming a guess.
+    pub fn submit_guess(&mut self, input: &str) -> Result<Round, GuessError> {
+        let guess = parse_guess(input, &self.config)?;
+        let hints = get_hints(&guess, &self.secret);
+        let round = (guess, hints);
+        self.history.push(round.clone());
+        Ok(round)
+    }
+
+    /// How many guesses have been played so far.
+    pub fn attempts(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Whether the most recent guess matched the secret code exactly.
+    pub fn is_won(&self) -> bool {
+        self.history.last().is_some_and(|(guess, _)| is_correct(guess, &self.secret))
+    }
+
+    /// Whether the player is out of guesses without having won.
+    pub fn is_lost(&self) -> bool {
+        !self.is_won() && self.attempts() >= self.config.max_guesses
+    }
+
+    /// Suggest a next guess consistent with every hint in [Game::history] so far:
+    /// the same "assume a guess is the answer, keep only codes that would have
+    /// produced the hints you were given" idea [best_guess] uses for the
+    /// computer-guesser mode, run against the player's own feedback instead.
+    /// Returns `None` if the history itself is inconsistent -- or, on a board
+    /// large enough that [uses_random_sampling] is true, if bounded random
+    /// sampling simply failed to turn up a survivor despite legitimate play.
+    pub fn hint(&self) -> Option<Vec<Color>> {
+        if self.history.is_empty() {
+            return Some(opening_guess(&self.config));
+        }
+        let possible = consistent_codes(&self.config, &self.history);
+        if possible.is_empty() {
+            return None;
+        }
+        let guess = best_guess(&self.config, &possible);
+        // best_guess may pick a code outside `possible` when it splits them
+        // better; a hint stays consistent, so fall back to a surviving code.
+        let guess = if possible.contains(&guess) { guess } else { possible[0] };
+        Some(decode_code(guess, self.config.code_length))
+    }
+
+    /// How many guesses a bounded consistency-filtering solver needs to crack
+    /// this game's secret code, without revealing what the code is; see
+    /// [solve_secret] for the value returned when it does not crack it. Lets a code
+    /// be rated as unusually easy or hard to guess. Not a guaranteed-optimal
+    /// solver: [best_guess] and [narrow] both trade a little precision for
+    /// staying responsive on large boards (see [uses_random_sampling]), so the
+    /// reported count is exact on boards small enough to search exhaustively,
+    /// and only an estimate beyond that.
+    pub fn rating(&self) -> usize {
+        solve_secret(&self.config, &self.secret)
+    }
+}
+
+// A code is packed into a `u64` with one fixed-width field per peg (so up to
+// twelve pegs, `64 / PEG_BITS`), each field holding a 1-based color index; a
+// field of `0` marks an empty peg past the end of the code. This lets the
+// solver compute feedback and bucket thousands of codes without allocating a
+// `Vec` per comparison. The field width caps the palette at `2^PEG_BITS - 1`
+// colors, which comfortably covers [ALL_COLORS]'s twenty.
+
+/// The number of bits used to pack each peg; wide enough for up to 31 colors
+/// (value `0` is the empty-peg sentinel) while still fitting twelve pegs --
+/// far more than [GameConfig::code_length] ever needs -- into a `u64`.
+const PEG_BITS: u32 = 5;
+
+/// The number of peg fields a packed `u64` has room for.
+const PEG_SLOTS: u32 = 64 / PEG_BITS;
+
+/// Masks off a single peg field.
+const PEG_MASK: u64 = (1 << PEG_BITS) - 1;
+
+/// The 1-based field value used to encode `color`; see [encode_code].
+fn color_index(color: &Color) -> u64 {
+    ALL_COLORS.iter().position(|c| c == color).unwrap() as u64 + 1
+}
+
+/// Pack a code into a `u64`, one field per peg, low peg in the low bits.
+pub fn encode_code(code: &[Color]) -> u64 {
+    code.iter().enumerate()
+        .fold(0u64, |packed, (i, color)| packed | (color_index(color) << (i as u32 * PEG_BITS)))
+}
+
+/// Unpack the first `length` pegs of a packed code back into colors.
+pub fn decode_code(packed: u64, length: usize) -> Vec<Color> {
+    (0..length)
+        .map(|i| ALL_COLORS[(((packed >> (i as u32 * PEG_BITS)) & PEG_MASK) - 1) as usize])
+        .collect()
+}
+
+/// Count the `(correct, misplaced)` pegs a guess scores against a code in a single
+/// pass over the [PEG_SLOTS] fields, using fixed per-color count arrays instead of
+/// the `used` boolean scan in [get_hints]. Empty pegs (field `0`) are ignored, so
+/// no length argument is needed.
+pub fn compute_feedback(guess: u64, code: u64) -> (u8, u8) {
+    let mut guess_counts = [0u8; 1 << PEG_BITS];
+    let mut code_counts = [0u8; 1 << PEG_BITS];
+    let mut correct = 0u8;
+    let (mut g, mut c) = (guess, code);
+    for _ in 0..PEG_SLOTS {
+        let gn = (g & PEG_MASK) as usize;
+        let cn = (c & PEG_MASK) as usize;
+        if gn != 0 && gn == cn {
+            correct += 1;
+        }
+        guess_counts[gn] += 1;
+        code_counts[cn] += 1;
+        g >>= PEG_BITS;
+        c >>= PEG_BITS;
+    }
+    // Sum over real colors only (skip the empty-peg slot 0); misplaced is the shared
+    // color count minus the pegs already counted as correctly placed.
+    let shared: u8 = (1..(1 << PEG_BITS)).map(|i| guess_counts[i].min(code_counts[i])).sum();
+    (correct, shared - correct)
+}
+
+/// The number of distinct feedback patterns, used to size a bucket array. Both peg
+/// counts fit in `0..=16`, so `(correct, misplaced)` packs into a single index.
+const FEEDBACK_PATTERNS: usize = 17 * 17;
+
+/// Pack a feedback pattern into a dense index in `0..FEEDBACK_PATTERNS`.
+fn feedback_to_index(correct: u8, misplaced: u8) -> usize {
+    correct as usize * 17 + misplaced as usize
+}
+
+/// The size of the full code space for `config` (`colors.len() ^ code_length`),
+/// used to decide whether scanning or materializing it in full is still cheap
+/// enough to bother with.
+fn code_space_size(config: &GameConfig) -> u64 {
+    (config.colors.len() as u64).pow(config.code_length as u32)
+}
+
+/// Above this many surviving codes, [best_guess] stops scoring candidates
+/// against `possible` altogether and just offers one of them -- scoring is
+/// `O(candidates * possible)`, and once both sides of that product are large
+/// (as they can be on boards with many colors and long codes) not even
+/// parallelizing it stays responsive. [narrow] also uses this to cap how many
+/// consistent codes it returns each round, for the same reason.
+const MAX_EXHAUSTIVE_SCAN: usize = 2_000;
+
+/// Above this many codes, [best_guess] no longer draws its candidate guesses
+/// from the full code space -- scoring is `O(candidates * possible)`, and
+/// scoring tens of thousands of candidates against each other gets expensive
+/// fast. Sized to comfortably cover the default nine-colors/length-five board
+/// (9^5 = 59049 codes), which Knuth minimax is meant to run on; past this size
+/// [best_guess] scores candidates drawn only from `possible` instead of the
+/// whole space.
+const FULL_SPACE_SCAN_LIMIT: u64 = 100_000;
+
+/// Above this many codes, even a short-circuiting, lazy scan of [code_space] can
+/// no longer be trusted to finish quickly: once the codes consistent with every
+/// round played shrink to only a handful (as they do late in a solve), `.filter`
+/// has to walk the *entire* space to confirm there is nothing left to find, and
+/// a length 10, nine-color board has 9^10 ≈ 3.5 billion of them. Past this size
+/// [narrow] switches to [random_narrow]'s bounded random sampling instead.
+const LARGE_SPACE_LIMIT: u64 = 5_000_000;
+
+/// How many random codes [random_narrow] draws before giving up on a board too
+/// large to scan exhaustively. Bounds its running time regardless of how rare
+/// a consistent code is, at the cost of occasionally missing one.
+const RANDOM_SAMPLE_ATTEMPTS: usize = 50_000;
+
+/// Lazily enumerate every code of the configured length over the active
+/// palette, in the same order [all_codes] would materialize them, without
+/// ever holding the whole space in memory at once.
+fn code_space(config: &GameConfig) -> impl Iterator<Item = u64> + '_ {
+    let indices: Vec<u64> = config.colors.iter().map(color_index).collect();
+    let length = config.code_length;
+    let base = indices.len() as u64;
+    (0..code_space_size(config)).map(move |mut n| {
+        let mut code = 0u64;
+        for peg in 0..length {
+            let digit = (n % base) as usize;
+            code |= indices[digit] << (peg as u32 * PEG_BITS);
+            n /= base;
+        }
+        code
+    })
+}
+
+/// Enumerate every possible code of the configured length over the active palette,
+/// already packed (see [encode_code]). For the default nine colors and length five
+/// that is 9^5 = 59049 codes. Only cheap for boards up to [FULL_SPACE_SCAN_LIMIT]
+/// codes; see [code_space] for a lazy version that never materializes more than
+/// it is asked to.
+pub fn all_codes(config: &GameConfig) -> Vec<u64> {
+    code_space(config).collect()
+}
+
+/// Whether `config`'s code space is too large for [narrow] to scan
+/// exhaustively, meaning it falls back to [random_narrow]'s bounded random
+/// sampling, which can occasionally miss a consistent code even when one
+/// exists. Frontends can use this to caveat solver/hint output on boards this
+/// large.
+pub fn uses_random_sampling(config: &GameConfig) -> bool {
+    code_space_size(config) > LARGE_SPACE_LIMIT
+}
+
+/// A fixed, sensible opening guess: the first half of the code one color, the rest
+/// a second color (the classic "1122" style opening generalized to any length).
+pub fn opening_guess(config: &GameConfig) -> Vec<Color> {
+    let half = config.code_length / 2;
+    (0..config.code_length)
+        .map(|i| if i < half { config.colors[0] } else { config.colors[1] })
+        .collect()
+}
+
+/// Choose the next guess by Knuth's minimax rule: for every candidate guess,
+/// partition `possible` by the feedback it would produce and score it by its
+/// largest partition; pick the guess with the smallest worst case, preferring
+/// one that is itself still possible. Candidates are drawn from the full code
+/// space -- true Knuth minimax, scoring every code in it, not just the
+/// surviving ones -- while that space is at most [FULL_SPACE_SCAN_LIMIT]
+/// codes, which comfortably covers the default board; past that size the
+/// scan falls back to candidates drawn from `possible` itself, and past
+/// [MAX_EXHAUSTIVE_SCAN] possible codes it skips scoring altogether to stay
+/// responsive. The scan runs in parallel with rayon.
+pub fn best_guess(config: &GameConfig, possible: &[u64]) -> u64 {
+    use std::collections::HashSet;
+    if possible.is_empty() {
+        // Nothing left to narrow down from -- either the history is genuinely
+        // inconsistent, or (on a board too large for [narrow] to scan
+        // exhaustively) random sampling simply didn't turn up a survivor.
+        // Either way there is no information left to act on; fall back to the
+        // fixed opening guess rather than panicking.
+        return encode_code(&opening_guess(config));
+    }
+    if possible.len() > MAX_EXHAUSTIVE_SCAN {
+        // Too many surviving codes to score pairwise; any of them is still
+        // consistent with every hint so far, so just offer one of those
+        // instead of scanning them all.
+        return possible[0];
+    }
+    let still_possible: HashSet<u64> = possible.iter().copied().collect();
+    let candidates: Vec<u64> = if code_space_size(config) <= FULL_SPACE_SCAN_LIMIT {
+        code_space(config).collect()
+    } else {
+        possible.to_vec()
+    };
+    candidates.par_iter()
+        .map(|&guess| {
+            let mut buckets = [0u32; FEEDBACK_PATTERNS];
+            for &code in possible {
+                let (correct, misplaced) = compute_feedback(guess, code);
+                buckets[feedback_to_index(correct, misplaced)] += 1;
+            }
+            let worst = buckets.iter().copied().max().unwrap_or(0);
+            // Tie-break towards a guess that could be the answer (false sorts first),
+            // then by value for a deterministic choice.
+            (worst, !still_possible.contains(&guess), guess)
+        })
+        .min()
+        .expect("possible is never empty")
+        .2
+}
+
+/// The `(correct, misplaced)` peg counts a round's [Hint]s represent.
+fn hint_counts(hints: &[Hint]) -> (u8, u8) {
+    let correct = hints.iter().filter(|&&h| h == Hint::CorrectlyPlaced).count() as u8;
+    let misplaced = hints.iter().filter(|&&h| h == Hint::CorrectColorButWrong).count() as u8;
+    (correct, misplaced)
+}
+
+/// Every code consistent with every `(guess, feedback)` pair in `rounds`,
+/// capped at [MAX_EXHAUSTIVE_SCAN] entries. Recomputed every time -- from the
+/// full code space on boards of up to [LARGE_SPACE_LIMIT] codes, from
+/// [random_narrow]'s samples on larger ones -- against the *whole* history at once, rather than
+/// narrowing down a previous, possibly-capped set round by round: the actual
+/// secret always satisfies every constraint, so it can only ever be missing
+/// from this round's capped output, never "lost" from an earlier round's
+/// surviving set that this round would otherwise have to build on -- which is
+/// what let capping silently turn a genuinely consistent board into a
+/// falsely-reported-inconsistent one.
+pub fn narrow(config: &GameConfig, rounds: &[(u64, (u8, u8))]) -> Vec<u64> {
+    if code_space_size(config) <= LARGE_SPACE_LIMIT {
+        code_space(config)
+            .filter(|&code| rounds.iter().all(|&(guess, feedback)| compute_feedback(guess, code) == feedback))
+            .take(MAX_EXHAUSTIVE_SCAN)
+            .collect()
+    } else {
+        random_narrow(config, rounds)
+    }
+}
+
+/// [narrow]'s fallback for boards too large to scan exhaustively: draw up to
+/// [RANDOM_SAMPLE_ATTEMPTS] random codes and keep whichever of them are
+/// consistent with every round, capped at [MAX_EXHAUSTIVE_SCAN]. Bounded
+/// running time instead of an exhaustive guarantee -- on a board this large it
+/// may return fewer codes than are actually consistent, or none at all, if the
+/// true secret is rare enough that random sampling does not happen to find it.
+fn random_narrow(config: &GameConfig, rounds: &[(u64, (u8, u8))]) -> Vec<u64> {
+    (0..RANDOM_SAMPLE_ATTEMPTS)
+        .map(|_| encode_code(&get_code(config)))
+        .filter(|&code| rounds.iter().all(|&(guess, feedback)| compute_feedback(guess, code) == feedback))
+        .take(MAX_EXHAUSTIVE_SCAN)
+        .collect()
+}
+
+/// Every code still consistent with `history`: codes that would have produced
+/// exactly the same hints as every guess already played, assuming each guess in
+/// turn were the answer.
+fn consistent_codes(config: &GameConfig, history: &[Round]) -> Vec<u64> {
+    let rounds: Vec<(u64, (u8, u8))> = history.iter()
+        .map(|(guess, hints)| (encode_code(guess), hint_counts(hints)))
+        .collect();
+    narrow(config, &rounds)
+}
+
+/// Play a full consistency-filtering solve against `secret` and report the
+/// number of guesses it takes, used to rate how hard a generated code is to
+/// crack. Mirrors the interactive computer-guesser loop, but plays both sides
+/// by scoring `guess` against `secret` directly instead of asking a human.
+/// Gives up after [GameConfig::max_guesses] guesses and then returns
+/// `max_guesses + 1`.
+fn solve_secret(config: &GameConfig, secret: &[Color]) -> usize {
+    let secret = encode_code(secret);
+    let mut guess = encode_code(&opening_guess(config));
+    let mut rounds: Vec<(u64, (u8, u8))> = vec![];
+    for attempt in 1..=config.max_guesses {
+        let feedback = compute_feedback(guess, secret);
+        if feedback.0 as usize == config.code_length {
+            return attempt;
+        }
+        rounds.push((guess, feedback));
+        let possible = narrow(config, &rounds);
+        guess = best_guess(config, &possible);
+    }
+    config.max_guesses + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Color::{Blue, Brown, Green, None, Red};
+
+    fn test_config() -> GameConfig {
+        GameConfig { code_length: 4, ..GameConfig::default() }
+    }
+
+    #[test]
+    fn compute_feedback_all_correct() {
+        let code = encode_code(&[Red, Green, Blue, Brown]);
+        assert_eq!(compute_feedback(code, code), (4, 0));
+    }
+
+    #[test]
+    fn compute_feedback_all_misplaced() {
+        let guess = encode_code(&[Red, Green, Blue, Brown]);
+        let code = encode_code(&[Green, Blue, Brown, Red]);
+        assert_eq!(compute_feedback(guess, code), (0, 4));
+    }
+
+    #[test]
+    fn compute_feedback_duplicate_colors_cap_misplaced_at_secrets_count() {
+        // The secret has two Reds; a guess offering three Reds can only ever
+        // be credited for the two the secret actually has.
+        let guess = encode_code(&[Red, Green, Red, Red]);
+        let code = encode_code(&[Red, Red, Green, Blue]);
+        assert_eq!(compute_feedback(guess, code), (1, 2));
+    }
+
+    #[test]
+    fn compute_feedback_none_pegs() {
+        let guess = encode_code(&[Blue, None, Red, Green]);
+        let code = encode_code(&[Red, None, Green, Blue]);
+        assert_eq!(compute_feedback(guess, code), (1, 3));
+    }
+
+    #[test]
+    fn compute_feedback_ignores_empty_peg_padding() {
+        // A 3-peg code leaves fields 3..12 as the empty-peg sentinel (0); two
+        // packed codes must not be credited with matching pegs past their length.
+        let guess = encode_code(&[Red, Green, Blue]);
+        let code = encode_code(&[Red, Brown, Brown]);
+        assert_eq!(compute_feedback(guess, code), (1, 0));
+    }
+
+    #[test]
+    fn compute_feedback_agrees_with_get_hints() {
+        let cases: [(&[Color], &[Color]); 3] = [
+            (&[Red, Green, Red, Red], &[Red, Red, Green, Blue]),
+            (&[Blue, None, Red, Green], &[Red, None, Green, Blue]),
+            (&[Red, Green, Blue, Brown], &[Red, Green, Blue, Brown]),
+        ];
+        for (guess, code) in cases {
+            let hints = get_hints(guess, code);
+            let from_packed = compute_feedback(encode_code(guess), encode_code(code));
+            assert_eq!(hint_counts(&hints), from_packed);
+        }
+    }
+
+    #[test]
+    fn game_submit_guess_detects_a_win() {
+        let secret = vec![Red, Green, Blue, Brown];
+        let mut game = Game::with_secret(test_config(), secret);
+        let (guess, hints) = game.submit_guess("red green blue brown").unwrap();
+        assert_eq!(guess, vec![Red, Green, Blue, Brown]);
+        assert_eq!(hints, vec![Hint::CorrectlyPlaced; 4]);
+        assert!(game.is_won());
+        assert_eq!(game.attempts(), 1);
+    }
+
+    #[test]
+    fn game_submit_guess_rejects_wrong_length() {
+        let mut game = Game::with_secret(test_config(), vec![Red, Green, Blue, Brown]);
+        let err = game.submit_guess("red green").unwrap_err();
+        assert_eq!(err, GuessError::WrongLength { expected: 4, got: 2 });
+        assert_eq!(game.attempts(), 0);
+    }
+
+    #[test]
+    fn game_submit_guess_rejects_color_outside_active_palette() {
+        let mut config = test_config();
+        config.colors = vec![Red, Brown];
+        let mut game = Game::with_secret(config, vec![Red, Brown, Red, Brown]);
+        let err = game.submit_guess("red yellow black blue").unwrap_err();
+        assert_eq!(err, GuessError::UnknownColor("yellow".to_string()));
+        assert_eq!(game.attempts(), 0);
+    }
+}
@@ -1,54 +1,117 @@
 use std::env;
 use std::io::Write;
-use std::iter::zip;
+use std::ops::RangeInclusive;
 use std::thread::sleep;
 use std::time::Duration;
-use rand::seq::IndexedRandom;
+use mestersind::{
+    best_guess, color_to_string, decode_code, encode_code, narrow,
+    opening_guess, uses_random_sampling, Color, Game, GameConfig, Hint, Round,
+    ALL_COLORS, DEFAULT_COLORS,
+};
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-/// Describes the colors that our Master mind game supports.
-/// You can use Red, Brown, Yellow, Green, Black, White, Orange, Blue or None.
-enum Color {
-    Red,
-    Brown,
-    Yellow,
-    Green,
-    Black,
-    White,
-    Orange,
-    Blue,
-    None,
+/// Build the config from command line flags if any are given (`--length`,
+/// `--colors`, `--guesses`, `--no-repeats`), otherwise ask the player for each
+/// option interactively. Missing options keep their [Default] value.
+fn gather_config() -> GameConfig {
+    if env::args().len() > 1 {
+        config_from_args()
+    } else {
+        config_from_prompt()
+    }
 }
 
-#[derive(PartialEq, Ord, Eq, PartialOrd)]
-/// Used when determining which part of the guess has same color and place, same color but wrong
-/// position or just plain wrong.
-enum Hint {
-    CorrectlyPlaced,
-    CorrectColorButWrong,
+/// Parse the config from command line flags, falling back to the defaults for
+/// anything not given. Unknown flags or out-of-range values abort the program.
+fn config_from_args() -> GameConfig {
+    let mut config = GameConfig::default();
+    let mut colors = DEFAULT_COLORS;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().expect("missing value for flag").parse::<usize>()
+            .expect("expected a number after flag");
+        match arg.as_str() {
+            "--length" => config.code_length = value(),
+            "--colors" => colors = value(),
+            "--guesses" => config.max_guesses = value(),
+            "--no-repeats" => config.allow_repeats = false,
+            // Handled separately by `wants_computer_guesser`/`wants_rating`; accept them here.
+            "--solve" => {}
+            "--rate" => {}
+            other => panic!("Unknown flag: {other}"),
+        }
+    }
+    assert!((4..=10).contains(&config.code_length), "code length must be 4-10");
+    assert!((2..=ALL_COLORS.len()).contains(&colors),
+            "number of colors must be 2-{}", ALL_COLORS.len());
+    assert!((7..=20).contains(&config.max_guesses), "max guesses must be 7-20");
+    config.colors = ALL_COLORS[..colors].to_vec();
+    config.reconcile();
+    config
 }
 
-/// Print the remaining tries. Goes from attempts to n (excluded).
-fn print_rows_of_dots(attempts: isize, n: isize) {
-    for i in attempts..n {
-        let row = i + 1;
-        println!("{row:2}: {}", "･".repeat(5));
+/// Ask the player for each option on the terminal, accepting the default when
+/// they just press enter.
+fn config_from_prompt() -> GameConfig {
+    let defaults = GameConfig::default();
+    let code_length = prompt_in_range("Code length", 4..=10, defaults.code_length);
+    let colors = prompt_in_range("Number of colors", 2..=ALL_COLORS.len(), DEFAULT_COLORS);
+    let max_guesses = prompt_in_range("Maximum guesses", 7..=20, defaults.max_guesses);
+    let allow_repeats = prompt_yes_no("Allow a color to repeat in the code?", true);
+    let mut config = GameConfig {
+        code_length,
+        colors: ALL_COLORS[..colors].to_vec(),
+        max_guesses,
+        allow_repeats,
+    };
+    config.reconcile();
+    config
+}
+
+/// Prompt for an integer in `range`, repeating until the player gives a valid one.
+/// An empty line accepts `default`.
+fn prompt_in_range(prompt: &str, range: RangeInclusive<usize>, default: usize) -> usize {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    loop {
+        print!("{prompt} [{}-{}, default {default}]: ", range.start(), range.end());
+        stdout.flush().unwrap();
+        let mut input = String::new();
+        stdin.read_line(&mut input).unwrap();
+        let input = input.trim();
+        if input.is_empty() { return default; }
+        match input.parse::<usize>() {
+            Ok(n) if range.contains(&n) => return n,
+            _ => println!("Please enter a number between {} and {}.", range.start(), range.end()),
+        }
+    }
+}
+
+/// Prompt for a yes/no answer, repeating until the player gives one. An empty line
+/// accepts `default`.
+fn prompt_yes_no(prompt: &str, default: bool) -> bool {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        print!("{prompt} [{hint}]: ");
+        stdout.flush().unwrap();
+        let mut input = String::new();
+        stdin.read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "" => return default,
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("Please answer yes or no."),
+        }
     }
 }
 
-/// Given a string *input*, get the color. Requires the string to be a valid color. See [Color].
-fn string_to_color(input: &str) -> Color {
-    match input.to_lowercase().as_str() {
-        "red" => Color::Red,
-        "brown" => Color::Brown,
-        "yellow" => Color::Yellow,
-        "green" => Color::Green,
-        "black" => Color::Black,
-        "white" => Color::White,
-        "orange" => Color::Orange,
-        "blue" => Color::Blue,
-        "none" => Color::None,
-        _ => panic!("Invalid color input") // precondition not fulfilled
+/// Print the remaining tries. Goes from attempts to n (excluded). Each row shows
+/// `length` empty slots.
+fn print_rows_of_dots(attempts: isize, n: isize, length: usize) {
+    for i in attempts..n {
+        let row = i + 1;
+        println!("{row:2}: {}", "･".repeat(length));
     }
 }
 
@@ -63,6 +126,17 @@ fn print_circle(color: &Color) {
         Color::White => "37",
         Color::Orange => "33",
         Color::Blue => "94",
+        Color::Purple => "38;5;93",
+        Color::Pink => "38;5;213",
+        Color::Cyan => "96",
+        Color::Gray => "38;5;250",
+        Color::Magenta => "35",
+        Color::Lime => "38;5;118",
+        Color::Teal => "38;5;30",
+        Color::Maroon => "38;5;88",
+        Color::Navy => "38;5;17",
+        Color::Olive => "38;5;58",
+        Color::Silver => "38;5;251",
         Color::None => {
             print!("◯");
             return;
@@ -84,7 +158,7 @@ fn clear_terminal() {
     };
     let mut command = std::process::Command::new(program);
     match family {
-        "windows" => command.args(&["/C", "cls"]),
+        "windows" => command.args(["/C", "cls"]),
         "unix" => command.arg("clear"),
         _ => panic!("Unknown family: {family}")
     };
@@ -92,13 +166,6 @@ fn clear_terminal() {
     child.wait().unwrap();
 }
 
-/// checks if the guess and code are the same.
-fn is_correct(guess: &Vec<Color>, code: &Vec<Color>) -> bool {
-    if guess.len() != code.len() { return false; }
-    let zip = zip(guess, code);
-    zip.fold(true, |acc, (guess, code)| { guess == code && acc })
-}
-
 /// Asks the user to press enter. Uses [std::io::stdio::read_line] to block and wait for the user.
 fn press_to_continue() {
     println!("Press enter to continue...");
@@ -107,131 +174,192 @@ fn press_to_continue() {
     stdin.read_line(&mut input).unwrap();
 }
 
-/// Given a string of colors, get the vector of [Color] colors.
-/// Requires the string to contain valid colors seperated by whitespace.
-/// Uses [string_to_color] to map the string to a [Color] color.
-fn get_colors(input: &String) -> Vec<Color> {
-    input.split_whitespace().map(string_to_color).collect()
-}
-
-
-
-/// If guess contains five valid colors (see [Color]) seperated by spaces then it will return true,
-/// otherwise false.
-fn validate_guess(guess: &String) -> bool {
-    const COLOR_SET: [&str; 9] = ["red", "brown", "yellow", "green", "black", "white", "orange",
-                                  "blue", "none"];
-    let colors = guess.split(" ");
-    let colors = colors.collect::<Vec<&str>>();
-    let size = colors.len();
-    if size != 5 { return false; }
-    colors.into_iter().fold(true,
-                            |acc, color| COLOR_SET.contains(&color) && acc)
-}
-
 fn main() {
     let stdin = std::io::stdin();
     let mut stdout = std::io::stdout();
 
     println!("Welcome to Mastermind! Let us get started.");
+    let config = gather_config();
+    if wants_computer_guesser() {
+        computer_guesser(&config);
+        return;
+    }
+    if wants_rating() {
+        rate_code(&config);
+        return;
+    }
     sleep(Duration::new(0, 750_000_000));
     clear_terminal();
 
-    let code = get_code();
+    let mut game = Game::new(config);
     println!("I have found my code. It is your job to guess it!");
-    // print_guess(&code); println!();
 
-    let n = 12;
-    let mut attempts = 0;
-    let mut history: Vec<(Vec<Color>, Vec<Hint>)> = vec!();
-    print_rows_of_dots(attempts, n);
-    println!("The colors are: Red, Brown, Yellow, Green, Black, White, Orange, Blue, None");
+    let n = game.config.max_guesses as isize;
+    print_rows_of_dots(0, n, game.config.code_length);
+    let color_names: Vec<&str> = game.config.colors.iter().map(color_to_string).collect();
+    println!("The colors are: {}", color_names.join(", "));
     println!("You guess by writing the colors you want to guess seperated by spaces. A guess");
-    println!("for Red, Brown, Yellow, Green and Black would look like:");
-    println!("> Red Brown Yellow Green Black");
+    let example: Vec<&str> = color_names.iter().cycle().take(game.config.code_length).copied().collect();
+    println!("for {} would look like:", example.join(", "));
+    println!("> {}", example.join(" "));
     println!("You will get hints: a '+' if you guessed a color correctly in the right place,");
     println!("or a '-' if you guessed a color, but it is in the wrong place.");
-    while attempts < n {
-        if attempts > 0 {
+    println!("Stuck? Type 'hint' instead of a guess for a suggested next guess.");
+    while (game.attempts() as isize) < n {
+        if game.attempts() > 0 {
             clear_terminal();
-            print_history(&history);
-            print_rows_of_dots(attempts, n);
+            print_history(&game.history);
+            print_rows_of_dots(game.attempts() as isize, n, game.config.code_length);
         }
         println!();
-        println!("You have {} tries remaining. Try and guess.", n - attempts);
+        println!("You have {} tries remaining. Try and guess.", n - game.attempts() as isize);
         print!("> ");
         stdout.flush().unwrap();
         let mut input = String::new();
         stdin.read_line(&mut input).unwrap();
-        let input = input.trim().to_lowercase();
-        if !validate_guess(&input) {
-            println!("Sorry, your guess was not valid. Please try again.");
+        if input.trim().eq_ignore_ascii_case("hint") {
+            match game.hint() {
+                Some(guess) => {
+                    print!("Hint, try: ");
+                    print_guess(&guess);
+                    println!();
+                }
+                None if uses_random_sampling(&game.config) => println!(
+                    "No hint available -- either the hints so far don't match any code, or this \
+                     board is large enough that the solver samples randomly instead of checking \
+                     exhaustively and simply got unlucky. Try again, or keep guessing without a hint."
+                ),
+                None => println!("No hint available -- the hints so far don't match any code."),
+            }
             continue;
         }
-        let guess: Vec<Color> = get_colors(&input);
+        let (guess, _) = match game.submit_guess(&input) {
+            Ok(round) => round,
+            Err(_) => {
+                println!("Sorry, your guess was not valid. Please try again.");
+                continue;
+            }
+        };
         print!("You guessed: ");
         print_guess(&guess);
-        attempts += 1;
-        if is_correct(&guess, &code) {
-            println!(", which is correct! Congratulations! It took {attempts} attempt{}.",
-                     pluralize(&attempts));
+        if game.is_won() {
+            println!(", which is correct! Congratulations! It took {} attempt{}.",
+                     game.attempts(), pluralize(&(game.attempts() as isize)));
             break;
         }
         println!(". Unfortunately that is not correct.");
-        let hints: Vec<Hint> = get_hints(&guess, &code);
         press_to_continue();
-        history.push((guess, hints));
     }
 }
 
-fn get_code() -> Vec<Color> {
-    let color_choices = [
-        Color::Red, Color::Brown, Color::Yellow, Color::Green, Color::Black, Color::White,
-        Color::Orange, Color::Blue, Color::None];
-    let mut colors = vec!();
-    let mut rng = rand::thread_rng();
-    for _ in 0..5 {
-        let &color = color_choices.choose(&mut rng).unwrap();
-        colors.push(color);
+/// Decide which side the computer plays: the `--solve` flag, or an interactive
+/// prompt when no flags were given.
+fn wants_computer_guesser() -> bool {
+    if env::args().len() > 1 {
+        env::args().any(|arg| arg == "--solve")
+    } else {
+        prompt_yes_no("Should the computer guess your secret code?", false)
+    }
+}
+
+/// Whether the player wants a hardness rating instead of a game: the `--rate` flag.
+fn wants_rating() -> bool {
+    env::args().any(|arg| arg == "--rate")
+}
+
+/// Generate a fresh secret code and report how many guesses a bounded
+/// consistency-filtering solver needs to crack it, without ever revealing the
+/// code itself.
+fn rate_code(config: &GameConfig) {
+    let game = Game::new(config.clone());
+    let guesses = game.rating();
+    println!("A freshly generated code takes {guesses} guess{} to crack with a consistency-filtering solver.",
+             if guesses == 1 { "" } else { "es" });
+    if uses_random_sampling(config) {
+        println!("This board is too large to search exhaustively, so the solver samples \
+                  randomly instead -- this rating is an estimate, not a guaranteed optimum.");
+    }
+    if guesses <= 2 {
+        println!("That is unusually easy.");
+    } else if guesses > config.max_guesses {
+        println!("That is unusually hard -- harder than your {} allotted guesses.", config.max_guesses);
     }
-    colors
-    // vec!(Color::Red, Color::Black, Color::None, Color::Red, Color::Green)
 }
 
-// Get hints based on guess and the correct code.
-fn get_hints(guess: &Vec<Color>, code: &Vec<Color>) -> Vec<Hint> {
-    let mut hints = vec!();
-    let mut used = [false; 5];
-    // CorrectColorButWrong can shadow CorrectlyPlaced, therefore
-    // we must first find all the CorrectlyPlaced before looking at
-    // CorrectColorButWrong.
-    for (i, color) in guess.iter().enumerate() {
-        if *color == code[i] {
-            hints.push(Hint::CorrectlyPlaced);
-            used[i] = true;
+/// The inverse of the normal game: the human keeps a secret code in mind and the
+/// program deduces it with a consistency-filtering strategy, asking for the peg
+/// counts after each guess. Reports rather than panics if the feedback is
+/// inconsistent -- though on a board large enough that [uses_random_sampling]
+/// is true, that report may really just be the solver's bounded random sampling
+/// failing to find the (still legitimate) survivor.
+fn computer_guesser(config: &GameConfig) {
+    let mut rounds: Vec<(u64, (u8, u8))> = vec![];
+    let mut guess = encode_code(&opening_guess(config));
+
+    println!("Think of a secret code and I will try to guess it.");
+    println!("After each guess, tell me how many pegs are correctly placed (+) and");
+    println!("how many are the right color but wrong place (-).");
+
+    for attempt in 1..=config.max_guesses {
+        print!("Guess {attempt}: ");
+        print_guess(&decode_code(guess, config.code_length));
+        println!();
+        let (correct, misplaced) = read_feedback(config.code_length);
+        if correct == config.code_length {
+            println!("I found your code in {attempt} guess{}!",
+                     if attempt == 1 { "" } else { "es" });
+            return;
+        }
+        rounds.push((guess, (correct as u8, misplaced as u8)));
+        let narrowed = narrow(config, &rounds);
+        match narrowed.len() {
+            0 if uses_random_sampling(config) => {
+                println!("I can't find a code matching all your feedback -- this board is large \
+                          enough that I search it by random sampling rather than exhaustively, so \
+                          this might just mean I got unlucky rather than your feedback being \
+                          inconsistent. Keep playing and I'll keep trying.");
+                rounds.pop();
+            }
+            0 => {
+                println!("That feedback is inconsistent with my earlier guesses -- \
+                          there is no code that matches all of it.");
+                return;
+            }
+            1 => {
+                print!("Your code must be: ");
+                print_guess(&decode_code(narrowed[0], config.code_length));
+                println!();
+                return;
+            }
+            n => {
+                println!("At least {n} codes still possible.");
+                guess = best_guess(config, &narrowed);
+            }
         }
     }
-    for (i, color) in guess.iter().enumerate() {
-        if *color == code[i] { continue }
-        zip(code, used)
-            .enumerate()
-            .filter(|(_, (code, used))| color == *code && !used)
-            .next().map(|(i, (_, _))| {
-                used[i] = true;
-                hints.push(Hint::CorrectColorButWrong)
-            });
+    println!("I ran out of guesses -- are you sure the feedback was correct?");
+}
+
+/// Read a `(correct, misplaced)` pair of peg counts from the player, repeating
+/// until the two values are in range and their sum does not exceed `length`.
+fn read_feedback(length: usize) -> (usize, usize) {
+    loop {
+        let correct = prompt_in_range("  correctly placed (+)", 0..=length, 0);
+        let misplaced = prompt_in_range("  right color, wrong place (-)", 0..=length, 0);
+        if correct + misplaced <= length {
+            return (correct, misplaced);
+        }
+        println!("Those add up to more than {length} pegs; please try again.");
     }
-    hints.sort();
-    hints
 }
 
-fn print_guess(guess: &Vec<Color>) {
+fn print_guess(guess: &[Color]) {
     for color in guess {
         print_circle(color);
     }
 }
 
-fn print_history(guesses: &Vec<(Vec<Color>, Vec<Hint>)>) {
+fn print_history(guesses: &[Round]) {
     for (i, (guess, hints)) in guesses.iter().enumerate() {
         let row = i + 1;
         print!("{row:2}: ");
@@ -242,7 +370,7 @@ fn print_history(guesses: &Vec<(Vec<Color>, Vec<Hint>)>) {
     }
 }
 
-fn print_hints(hints: &Vec<Hint>) {
+fn print_hints(hints: &[Hint]) {
     for hint in hints {
         print!("{}", match hint {
             Hint::CorrectlyPlaced => "+",